@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::plugin::PluginSource;
+use crate::server::{Server, Software};
+
+fn default_mem() -> u32 {
+    2048
+}
+
+fn default_port() -> u16 {
+    25565
+}
+
+/// A `server.toml` project file, parsed into the same shape `Server` expects
+/// so a dev-server definition can be committed instead of re-typed as flags.
+#[derive(Debug, Deserialize)]
+pub struct ServerConfig {
+    pub software: Software,
+    pub version: String,
+    #[serde(default = "default_mem")]
+    pub mem: u32,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub gui: bool,
+    #[serde(default)]
+    pub plugins: Vec<String>,
+    #[serde(default)]
+    pub properties: HashMap<String, String>,
+    #[serde(default)]
+    pub worlds: Vec<String>,
+}
+
+impl ServerConfig {
+    pub fn load(path: &PathBuf) -> Result<ServerConfig, String> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+
+        match toml::from_str(&contents) {
+            Ok(config) => Ok(config),
+            Err(e) => Err(format!("Failed to parse {}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn into_server(self, wd: PathBuf) -> Server {
+        let mut args = self.args;
+        if !self.gui {
+            args.push("--nogui".to_string());
+        }
+
+        let mut properties = self.properties;
+        properties.entry("server-port".to_string()).or_insert_with(|| self.port.to_string());
+
+        Server {
+            wd,
+            software: self.software,
+            version: self.version,
+            plugins: self.plugins.iter().map(|spec| PluginSource::parse(spec)).collect(),
+            args,
+            mem: self.mem,
+            properties,
+            worlds: self.worlds,
+            vanilla_manifest_entry_url: None,
+        }
+    }
+}
@@ -1,13 +1,22 @@
 use std::fs;
+use std::io::Write;
 use std::path::PathBuf;
 use std::process::exit;
 use clap::{CommandFactory, Parser, Subcommand};
 use libtermcolor::colors;
+use log::{debug, error, info, LevelFilter};
+use crate::config::ServerConfig;
+use crate::plugin::PluginSource;
 use crate::server::Software;
 use crate::server_manager::check_valid_version;
 
+mod config;
+mod network;
+mod plugin;
 mod server;
 mod server_manager;
+mod source;
+mod world;
 
 #[derive(Parser, Debug)]
 #[command(about, long_about, name = "mcdevkit", version)]
@@ -28,7 +37,7 @@ enum Commands {
         version: String,
 
         #[arg()]
-        plugins: Vec<PathBuf>,
+        plugins: Vec<String>,
 
         #[arg(short, long, default_value = "none")]
         working_directory: PathBuf,
@@ -45,82 +54,220 @@ enum Commands {
         #[arg(short, long, default_value = "25565")]
         port: u16,
 
+        #[arg(short, long)]
+        debug: bool,
+
+        #[arg(long, help = "Watch the plugins folder and restart the server on change")]
+        watch: bool
+    },
+
+    #[command(about = "Run a server defined by a server.toml project file")]
+    Run {
+        #[arg(short, long, default_value = "server.toml")]
+        config: PathBuf,
+
+        #[arg(short, long, default_value = "none")]
+        working_directory: PathBuf,
+
         #[arg(short, long)]
         debug: bool
+    },
+
+    #[command(about = "Zip a live world folder back out to a .zip archive")]
+    ExportWorld {
+        #[arg(required = true)]
+        name: String,
+
+        #[arg(short, long, default_value = ".")]
+        working_directory: PathBuf,
+
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    #[command(about = "Launch a proxy and its backend servers from a network.toml project file")]
+    Network {
+        #[arg(short, long, default_value = "network.toml")]
+        config: PathBuf,
+
+        #[arg(short, long, default_value = "none")]
+        working_directory: PathBuf,
+
+        #[arg(short, long)]
+        debug: bool
+    }
+}
+
+impl Commands {
+    fn debug_enabled(&self) -> bool {
+        match self {
+            Commands::Start { debug, .. } => *debug,
+            Commands::Run { debug, .. } => *debug,
+            Commands::ExportWorld { .. } => false,
+            Commands::Network { debug, .. } => *debug,
+        }
     }
 }
 
-pub fn send_info(msg: String) {
-    println!("{}[{}MC-SDK{}]{} {}{}", colors::bright_black().regular, colors::bright_green().regular, colors::bright_black().regular, colors::bright_green().regular, msg, colors::reset())
+fn init_logger(debug: bool) {
+    env_logger::Builder::new()
+        .filter_level(LevelFilter::Info)
+        .filter_module("mcdevkit", if debug { LevelFilter::Debug } else { LevelFilter::Info })
+        .format(|buf, record| {
+            let (label, color) = match record.level() {
+                log::Level::Error => ("MC-SDK", colors::bright_red().regular),
+                log::Level::Debug => ("Debug", colors::bright_yellow().regular),
+                _ => ("MC-SDK", colors::bright_green().regular),
+            };
+            writeln!(buf, "{}[{}{}{}]{} {}{}", colors::bright_black().regular, color, label, colors::bright_black().regular, color, record.args(), colors::reset())
+        })
+        .init();
+}
+
+fn prepare_working_directory(working_directory: &PathBuf) {
+    if working_directory != &PathBuf::from("none") {
+        if !working_directory.exists() {
+            if let Err(err) = fs::create_dir(working_directory.clone()) { error!("Error creating directory: {}", err) }
+        }
+
+        if !working_directory.is_dir() {
+            error!("You need to specify a Directory not a file");
+            exit(1)
+        }
+    }
 }
 
-pub fn send_debug(msg: String) {
-    println!("{}[{}Debug{}]{} {}{}", colors::bright_black().regular, colors::bright_yellow().regular, colors::bright_black().regular, colors::bright_yellow().regular, msg, colors::reset())
+async fn run_server(mut server: server::Server, watch: bool) {
+    if let Err(err) = server.init_server().await {
+        error!("Error initializing server: {}", err);
+        exit(1);
+    }
+
+    if let Err(err) = server.start_server(watch).await {
+        error!("Error starting server: {}", err);
+        exit(1);
+    }
+
+    println!("\n");
+    info!("Server Stopped.")
 }
 
 #[tokio::main]
 async fn main() {
     let args = Args::parse();
-    if let Some(Commands::Start { software, version, plugins, working_directory, mut args, mem, gui, port, debug }) = args.command {
-        if !check_valid_version(&version).await {
-            exit(1)
-        }
 
-        if !gui {
-            args.push("--nogui".to_string())
-        }
+    let debug_enabled = args.command.as_ref().map(Commands::debug_enabled).unwrap_or(false);
+    init_logger(debug_enabled);
 
-        if port != 25565 {
-            args.push(format!("--port={}", port))
-        }
+    match args.command {
+        Some(Commands::Start { software, version, plugins, working_directory, mut args, mem, gui, port, debug, watch }) => {
+            let vanilla_manifest_entry_url = match check_valid_version(&version).await {
+                Some(url) => url,
+                None => exit(1),
+            };
 
-        if working_directory != PathBuf::from("none") {
-            if !working_directory.exists() {
-                if let Err(err) = fs::create_dir(working_directory.clone()) { eprintln!("Error creating directory: {}", err) }
+            if !gui {
+                args.push("--nogui".to_string())
             }
 
-            if !working_directory.is_dir() {
-                eprintln!("Error: You need to specify a Directory not a file");
-                exit(1)
+            prepare_working_directory(&working_directory);
+
+            if debug {
+                debug!("Software: {:?}", software);
+                debug!("Version: {}", version);
+                debug!("Args: {:?}", args);
+                debug!("Plugins: {:?}", plugins);
+                debug!("Watch: {}", watch);
             }
+
+            let plugins = plugins.iter().map(|spec| PluginSource::parse(spec)).collect();
+
+            let mut properties = std::collections::HashMap::new();
+            properties.insert("server-port".to_string(), port.to_string());
+
+            let server = server::Server {
+                wd: working_directory,
+                software,
+                version,
+                plugins,
+                args,
+                mem,
+                properties,
+                worlds: Vec::new(),
+                vanilla_manifest_entry_url: Some(vanilla_manifest_entry_url),
+            };
+
+            run_server(server, watch).await;
+            exit(0)
         }
+        Some(Commands::Run { config, working_directory, debug }) => {
+            let config = match ServerConfig::load(&config) {
+                Ok(config) => config,
+                Err(err) => {
+                    error!("{}", err);
+                    exit(1)
+                }
+            };
 
-        if debug {
-            send_debug(format!("Software: {:?}", software));
-            send_debug(format!("Version: {}", version));
-            send_debug("Args: ".parse().unwrap());
-            for arg in args.clone() {
-                println!(" > {}{}", colors::bright_yellow().regular, arg);
-            }
+            let vanilla_manifest_entry_url = match check_valid_version(&config.version).await {
+                Some(url) => url,
+                None => exit(1),
+            };
 
-            send_debug("Plugins: ".parse().unwrap());
-            for plugin in plugins.clone() {
-                println!(" > {}{}", colors::bright_yellow().regular, plugin.file_name().unwrap().to_str().unwrap());
+            prepare_working_directory(&working_directory);
+
+            if debug {
+                debug!("Software: {:?}", config.software);
+                debug!("Version: {}", config.version);
             }
+
+            let mut server = config.into_server(working_directory);
+            server.vanilla_manifest_entry_url = Some(vanilla_manifest_entry_url);
+            run_server(server, false).await;
+            exit(0)
         }
+        Some(Commands::ExportWorld { name, working_directory, output }) => {
+            let output = output.unwrap_or_else(|| PathBuf::from(format!("{}.zip", name)));
 
-        let mut server = server::Server {
-            wd: working_directory,
-            software,
-            version,
-            plugins,
-            args,
-            mem,
-        };
-
-        server.init_server().await;
-        if let Err(err) = server.start_server().await {
-            eprintln!("Error starting server: {}", err);
-            exit(1);
+            match world::export_world(&working_directory, &name, &output) {
+                Ok(()) => info!("Exported {} to {}.", name, output.display()),
+                Err(err) => {
+                    error!("{}", err);
+                    exit(1)
+                }
+            }
+
+            exit(0)
         }
+        Some(Commands::Network { config, working_directory, debug }) => {
+            let config = match network::NetworkConfig::load(&config) {
+                Ok(config) => config,
+                Err(err) => {
+                    error!("{}", err);
+                    exit(1)
+                }
+            };
 
-        println!("\n");
-        send_info("Server Stopped.".to_string())
-    }
+            if debug {
+                debug!("Proxy: {:?}", config.proxy.software);
+                debug!("Servers: {}", config.servers.len());
+            }
+
+            if let Err(err) = network::run_network(config, working_directory).await {
+                error!("{}", err);
+                exit(1)
+            }
 
-    if std::env::args().len() == 1 {
-        Args::command().print_help().unwrap();
-        exit(0);
+            println!("\n");
+            info!("Network Stopped.");
+            exit(0)
+        }
+        None => {
+            if std::env::args().len() == 1 {
+                Args::command().print_help().unwrap();
+                exit(0);
+            }
+            exit(0)
+        }
     }
-    exit(0)
 }
\ No newline at end of file
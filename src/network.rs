@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use log::info;
+use serde::Deserialize;
+use tokio::process::Child;
+
+use crate::server::{build_java_command, wait_for_children_or_interrupt, Server, Software};
+use crate::server_manager::{createdir, download_file, generate_random_uuid, get_temp_folder};
+
+fn default_mem() -> u32 {
+    2048
+}
+
+fn default_proxy_port() -> u16 {
+    25577
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxySoftware {
+    Velocity,
+    Bungeecord,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProxyConfig {
+    pub software: ProxySoftware,
+    #[serde(default = "default_proxy_port")]
+    pub port: u16,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NetworkServerConfig {
+    pub software: Software,
+    pub version: String,
+    #[serde(default = "default_mem")]
+    pub mem: u32,
+    pub port: u16,
+}
+
+/// A `network.toml` project file describing a proxy and its backend servers,
+/// so cross-server plugin behavior (transfers, messaging) can be tested locally.
+#[derive(Debug, Deserialize)]
+pub struct NetworkConfig {
+    pub proxy: ProxyConfig,
+    pub servers: HashMap<String, NetworkServerConfig>,
+}
+
+impl NetworkConfig {
+    pub fn load(path: &PathBuf) -> Result<NetworkConfig, String> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+
+        match toml::from_str(&contents) {
+            Ok(config) => Ok(config),
+            Err(e) => Err(format!("Failed to parse {}: {}", path.display(), e)),
+        }
+    }
+}
+
+async fn resolve_papermc_project_download_url(project: &str) -> Result<String, String> {
+    let versions_url = format!("https://api.papermc.io/v2/projects/{}", project);
+    let versions_response = match reqwest::get(&versions_url).await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to fetch {} versions: {}", project, e)),
+    };
+
+    #[derive(Debug, Deserialize)]
+    struct ProjectVersions {
+        versions: Vec<String>,
+    }
+
+    let versions: ProjectVersions = match versions_response.json().await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to parse {} versions: {}", project, e)),
+    };
+
+    let version = match versions.versions.last() {
+        Some(version) => version.clone(),
+        None => return Err(format!("No versions available for {}.", project)),
+    };
+
+    let builds_url = format!("https://api.papermc.io/v2/projects/{}/versions/{}/builds", project, version);
+    let builds_response = match reqwest::get(&builds_url).await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to fetch {} builds: {}", project, e)),
+    };
+
+    #[derive(Debug, Deserialize)]
+    struct ProjectBuilds {
+        builds: Vec<ProjectBuild>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct ProjectBuild {
+        build: u32,
+        downloads: ProjectBuildDownloads,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct ProjectBuildDownloads {
+        application: ProjectBuildDownloadEntry,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct ProjectBuildDownloadEntry {
+        name: String,
+    }
+
+    let builds: ProjectBuilds = match builds_response.json().await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to parse {} builds: {}", project, e)),
+    };
+
+    let build = match builds.builds.last() {
+        Some(build) => build,
+        None => return Err(format!("No builds available for {} {}.", project, version)),
+    };
+
+    Ok(format!(
+        "https://api.papermc.io/v2/projects/{}/versions/{}/builds/{}/downloads/{}",
+        project, version, build.build, build.downloads.application.name
+    ))
+}
+
+async fn resolve_proxy_download_url(software: ProxySoftware) -> Result<String, String> {
+    match software {
+        ProxySoftware::Velocity => resolve_papermc_project_download_url("velocity").await,
+        ProxySoftware::Bungeecord => Ok("https://ci.md-5.net/job/BungeeCord/lastSuccessfulBuild/artifact/bootstrap/target/BungeeCord.jar".to_string()),
+    }
+}
+
+fn write_velocity_config(proxy_dir: &Path, proxy_port: u16, backends: &[(String, u16)]) -> Result<(), String> {
+    let mut servers = String::new();
+    let mut try_order = String::new();
+    for (name, port) in backends {
+        servers.push_str(&format!("{} = \"127.0.0.1:{}\"\n", name, port));
+        if !try_order.is_empty() {
+            try_order.push_str(", ");
+        }
+        try_order.push_str(&format!("\"{}\"", name));
+    }
+
+    let contents = format!(
+        "config-version = \"2.0\"\nbind = \"0.0.0.0:{}\"\nmotd = \"A Velocity Server\"\nshow-max-players = 500\nonline-mode = false\nforce-key-authentication = true\nplayer-info-forwarding-mode = \"none\"\n\n[servers]\n{}try = [{}]\n\n[forced-hosts]\n\n[advanced]\n\n[query]\nenabled = false\n",
+        proxy_port, servers, try_order
+    );
+
+    let mut path = proxy_dir.to_path_buf();
+    path.push("velocity.toml");
+
+    std::fs::write(&path, contents).map_err(|e| format!("Error writing velocity.toml: {}", e))
+}
+
+fn write_bungeecord_config(proxy_dir: &Path, proxy_port: u16, backends: &[(String, u16)]) -> Result<(), String> {
+    let mut server_connections = String::new();
+    let mut priorities = String::new();
+    for (name, port) in backends {
+        server_connections.push_str(&format!("  {}:\n    address: localhost:{}\n    motd: A Minecraft Server\n    restricted: false\n", name, port));
+        priorities.push_str(&format!("  - {}\n", name));
+    }
+
+    let contents = format!(
+        "server_connections:\n{}listeners:\n- query_port: {}\n  motd: A BungeeCord Server\n  priorities:\n{}  bind_local_address: true\n  host: 0.0.0.0:{}\n  max_players: 500\n  tab_list: GLOBAL_PING\n  force_default_server: false\nonline_mode: false\nlog_commands: false\n",
+        server_connections, proxy_port, priorities, proxy_port
+    );
+
+    let mut path = proxy_dir.to_path_buf();
+    path.push("config.yml");
+
+    std::fs::write(&path, contents).map_err(|e| format!("Error writing config.yml: {}", e))
+}
+
+pub async fn run_network(config: NetworkConfig, mut wd: PathBuf) -> Result<(), String> {
+    if wd == PathBuf::from("none") {
+        wd = get_temp_folder()?;
+        wd.push("mcdevkit");
+        createdir(wd.clone())?;
+        wd.push(format!("network-{}", generate_random_uuid()));
+        createdir(wd.clone())?;
+    } else {
+        wd = wd.canonicalize().map_err(|_| "Failed to get the full path.".to_string())?;
+    }
+
+    let mut proxy_dir = wd.clone();
+    proxy_dir.push("proxy");
+    createdir(proxy_dir.clone())?;
+
+    info!("Downloading Proxy Software.");
+    let proxy_url = resolve_proxy_download_url(config.proxy.software).await?;
+
+    download_file(&proxy_url, &proxy_dir, "proxy.jar").await.map_err(|e| format!("Error downloading proxy software: {}", e))?;
+
+    let mut backends: Vec<(String, u16, Server)> = Vec::new();
+    for (name, backend) in &config.servers {
+        let mut backend_wd = wd.clone();
+        backend_wd.push(name);
+        createdir(backend_wd.clone())?;
+
+        let mut properties = HashMap::new();
+        properties.insert("server-port".to_string(), backend.port.to_string());
+        properties.insert("online-mode".to_string(), "false".to_string());
+
+        let mut server = Server {
+            wd: backend_wd,
+            software: backend.software,
+            version: backend.version.clone(),
+            plugins: Vec::new(),
+            args: vec!["--nogui".to_string()],
+            mem: backend.mem,
+            properties,
+            worlds: Vec::new(),
+            vanilla_manifest_entry_url: None,
+        };
+
+        info!("Initializing {} Server.", name);
+        server.init_server().await.map_err(|e| format!("Failed to initialize {}: {}", name, e))?;
+
+        backends.push((name.clone(), backend.port, server));
+    }
+
+    let backend_ports: Vec<(String, u16)> = backends.iter().map(|(name, port, _)| (name.clone(), *port)).collect();
+
+    match config.proxy.software {
+        ProxySoftware::Velocity => write_velocity_config(&proxy_dir, config.proxy.port, &backend_ports),
+        ProxySoftware::Bungeecord => write_bungeecord_config(&proxy_dir, config.proxy.port, &backend_ports),
+    }?;
+
+    info!("Starting Network.");
+
+    let mut children: Vec<Child> = Vec::new();
+
+    let mut proxy_command = build_java_command(&proxy_dir, "proxy.jar", None, &[]);
+    proxy_command.stdout(Stdio::inherit()).stdin(Stdio::inherit()).stderr(Stdio::inherit());
+    let proxy_child = proxy_command.spawn().map_err(|e| format!("Error starting proxy: {}", e))?;
+    children.push(proxy_child);
+
+    for (name, _, server) in &backends {
+        let mut command = build_java_command(&server.wd, "server.jar", Some(server.mem), &server.args);
+        command.stdout(Stdio::inherit()).stdin(Stdio::inherit()).stderr(Stdio::inherit());
+
+        let child = command.spawn().map_err(|e| format!("Error starting {}: {}", name, e))?;
+        children.push(child);
+    }
+
+    wait_for_children_or_interrupt(&mut children).await.map_err(|e| format!("Error hooking signal: {}", e))
+}
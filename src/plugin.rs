@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use log::{error, info};
+use serde::Deserialize;
+
+use crate::server::Software;
+use crate::server_manager::{copy_file_to_folder, download_file};
+
+/// Where a plugin/mod jar should come from: already on disk, or resolved
+/// from a remote repository by slug at `init_server` time.
+#[derive(Debug, Clone)]
+pub enum PluginSource {
+    Local(PathBuf),
+    Modrinth { slug: String, version: Option<String> },
+    Hangar { slug: String, version: Option<String> },
+}
+
+impl PluginSource {
+    /// Parses a CLI/config plugin spec such as `modrinth:spark`,
+    /// `modrinth:spark@1.10.53`, `hangar:ViaVersion`, or a plain file path.
+    pub fn parse(spec: &str) -> PluginSource {
+        if let Some(rest) = spec.strip_prefix("modrinth:") {
+            let (slug, version) = split_slug_version(rest);
+            return PluginSource::Modrinth { slug, version };
+        }
+
+        if let Some(rest) = spec.strip_prefix("hangar:") {
+            let (slug, version) = split_slug_version(rest);
+            return PluginSource::Hangar { slug, version };
+        }
+
+        PluginSource::Local(PathBuf::from(spec))
+    }
+}
+
+fn split_slug_version(spec: &str) -> (String, Option<String>) {
+    match spec.split_once('@') {
+        Some((slug, version)) => (slug.to_string(), Some(version.to_string())),
+        None => (spec.to_string(), None),
+    }
+}
+
+fn modrinth_loader_name(software: Software) -> &'static str {
+    match software {
+        Software::Paper => "paper",
+        Software::Purpur => "purpur",
+        Software::Spigot => "spigot",
+        Software::Fabric => "fabric",
+        Software::Forge => "forge",
+        Software::Quilt => "quilt",
+        Software::Vanilla => "minecraft",
+    }
+}
+
+fn hangar_platform(software: Software) -> Option<&'static str> {
+    match software {
+        Software::Paper | Software::Purpur | Software::Spigot => Some("PAPER"),
+        Software::Vanilla | Software::Fabric | Software::Forge | Software::Quilt => None,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ModrinthVersion {
+    version_number: String,
+    game_versions: Vec<String>,
+    loaders: Vec<String>,
+    date_published: String,
+    files: Vec<ModrinthFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModrinthFile {
+    url: String,
+    filename: String,
+    primary: bool,
+}
+
+async fn resolve_modrinth_download(slug: &str, version: Option<&str>, server_version: &str, loader: &str) -> Result<(String, String), String> {
+    let url = format!("https://api.modrinth.com/v2/project/{}/version", slug);
+    let response = match reqwest::get(&url).await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to fetch Modrinth versions for {}: {}", slug, e)),
+    };
+
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch Modrinth versions for {}: Status code {}", slug, response.status()));
+    }
+
+    let versions: Vec<ModrinthVersion> = match response.json().await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to parse Modrinth versions for {}: {}", slug, e)),
+    };
+
+    let mut matching: Vec<ModrinthVersion> = versions.into_iter()
+        .filter(|v| v.game_versions.iter().any(|gv| gv == server_version) && v.loaders.iter().any(|l| l == loader))
+        .collect();
+
+    matching.sort_by(|a, b| b.date_published.cmp(&a.date_published));
+
+    let chosen = match version {
+        Some(requested) => matching.into_iter().find(|v| v.version_number == requested),
+        None => matching.into_iter().next(),
+    };
+
+    let chosen = match chosen {
+        Some(v) => v,
+        None => return Err(format!("No matching Modrinth version found for {} (server version {}, loader {}).", slug, server_version, loader)),
+    };
+
+    match chosen.files.iter().find(|f| f.primary) {
+        Some(f) => Ok((f.url.clone(), f.filename.clone())),
+        None => Err(format!("Modrinth version {} of {} has no primary file.", chosen.version_number, slug)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct HangarVersionList {
+    result: Vec<HangarVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HangarVersion {
+    name: String,
+    downloads: HashMap<String, HangarDownload>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HangarDownload {
+    #[serde(rename = "downloadUrl")]
+    download_url: Option<String>,
+    #[serde(rename = "fileInfo")]
+    file_info: HangarFileInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct HangarFileInfo {
+    name: String,
+}
+
+async fn resolve_hangar_download(slug: &str, version: Option<&str>, platform: &str) -> Result<(String, String), String> {
+    let url = format!("https://hangar.papermc.io/api/v1/projects/{}/versions", slug);
+    let response = match reqwest::get(&url).await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to fetch Hangar versions for {}: {}", slug, e)),
+    };
+
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch Hangar versions for {}: Status code {}", slug, response.status()));
+    }
+
+    let list: HangarVersionList = match response.json().await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to parse Hangar versions for {}: {}", slug, e)),
+    };
+
+    let chosen = match version {
+        Some(requested) => list.result.into_iter().find(|v| v.name == requested),
+        None => list.result.into_iter().next(),
+    };
+
+    let chosen = match chosen {
+        Some(v) => v,
+        None => return Err(format!("No matching Hangar version found for {}.", slug)),
+    };
+
+    let download = match chosen.downloads.get(platform) {
+        Some(d) => d,
+        None => return Err(format!("Hangar version {} of {} has no {} download.", chosen.name, slug, platform)),
+    };
+
+    match &download.download_url {
+        Some(download_url) => Ok((download_url.clone(), download.file_info.name.clone())),
+        None => Err(format!("Hangar version {} of {} has no direct download URL for {}.", chosen.name, slug, platform)),
+    }
+}
+
+pub async fn copy_plugins(plugins: Vec<PluginSource>, plugins_folder: PathBuf, server_version: &str, software: Software) {
+    if !plugins_folder.exists() {
+        error!("Destination folder does not exist: {:?}", plugins_folder);
+        return;
+    }
+
+    if !plugins_folder.is_dir() {
+        error!("Destination path is not a directory: {:?}", plugins_folder);
+        return;
+    }
+
+    for plugin in plugins {
+        match plugin {
+            PluginSource::Local(path) => {
+                if !path.exists() {
+                    error!("{:?} does not exist. Skipping...", path);
+                    continue;
+                }
+
+                if path.is_file() {
+                    match copy_file_to_folder(path.clone(), plugins_folder.clone()) {
+                        Ok(()) => info!("{} moved to plugins Folder.", path.display()),
+                        Err(e) => error!("Failed to copy {}: {}", path.display(), e),
+                    }
+                } else {
+                    error!("{:?} is not a file. Skipping...", path);
+                }
+            }
+            PluginSource::Modrinth { slug, version } => {
+                let loader = modrinth_loader_name(software);
+                match resolve_modrinth_download(&slug, version.as_deref(), server_version, loader).await {
+                    Ok((url, file_name)) => match download_file(&url, &plugins_folder, &file_name).await {
+                        Ok(()) => info!("{} downloaded from Modrinth.", slug),
+                        Err(e) => error!("Failed to download {} from Modrinth: {}", slug, e),
+                    },
+                    Err(e) => error!("Failed to resolve {} on Modrinth: {}", slug, e),
+                }
+            }
+            PluginSource::Hangar { slug, version } => {
+                let platform = match hangar_platform(software) {
+                    Some(platform) => platform,
+                    None => {
+                        error!("Hangar does not support {:?} servers. Skipping {}.", software, slug);
+                        continue;
+                    }
+                };
+
+                match resolve_hangar_download(&slug, version.as_deref(), platform).await {
+                    Ok((url, file_name)) => match download_file(&url, &plugins_folder, &file_name).await {
+                        Ok(()) => info!("{} downloaded from Hangar.", slug),
+                        Err(e) => error!("Failed to download {} from Hangar: {}", slug, e),
+                    },
+                    Err(e) => error!("Failed to resolve {} on Hangar: {}", slug, e),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_slug_version_splits_on_at() {
+        assert_eq!(split_slug_version("spark@1.10.53"), ("spark".to_string(), Some("1.10.53".to_string())));
+    }
+
+    #[test]
+    fn split_slug_version_without_at_has_no_version() {
+        assert_eq!(split_slug_version("spark"), ("spark".to_string(), None));
+    }
+
+    #[test]
+    fn parse_modrinth_spec() {
+        match PluginSource::parse("modrinth:spark@1.10.53") {
+            PluginSource::Modrinth { slug, version } => {
+                assert_eq!(slug, "spark");
+                assert_eq!(version, Some("1.10.53".to_string()));
+            }
+            other => panic!("expected Modrinth, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_hangar_spec_without_version() {
+        match PluginSource::parse("hangar:ViaVersion") {
+            PluginSource::Hangar { slug, version } => {
+                assert_eq!(slug, "ViaVersion");
+                assert_eq!(version, None);
+            }
+            other => panic!("expected Hangar, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_plain_path_is_local() {
+        match PluginSource::parse("./plugins/spark.jar") {
+            PluginSource::Local(path) => assert_eq!(path, PathBuf::from("./plugins/spark.jar")),
+            other => panic!("expected Local, got {:?}", other),
+        }
+    }
+}
@@ -1,98 +1,231 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs::File;
 use std::io::Write;
-use std::path::PathBuf;
-use std::process::{exit, Stdio};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use clap::ValueEnum;
-use tokio::process::Command;
+use futures_util::future::select_all;
+use log::{error, info};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::{Child, Command};
 use tokio::signal::unix::{signal, SignalKind};
-use crate::send_info;
-use crate::server_manager::{copy_plugins, createdir, download_server_software, generate_random_uuid, get_temp_folder};
+use tokio::sync::mpsc;
+use crate::plugin::{copy_plugins, PluginSource};
+use crate::server_manager::{createdir, download_server_software, generate_random_uuid, get_temp_folder, write_server_properties};
+use crate::world::provision_worlds;
 
-#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Software {
     Paper,
+    Vanilla,
+    Purpur,
+    Fabric,
+    Forge,
+    Quilt,
+    Spigot,
 }
 
 pub struct Server {
     pub wd: PathBuf,
     pub software: Software,
     pub version: String,
-    pub plugins: Vec<PathBuf>,
+    pub plugins: Vec<PluginSource>,
     pub args: Vec<String>,
-    pub mem: u32
+    pub mem: u32,
+    pub properties: HashMap<String, String>,
+    pub worlds: Vec<String>,
+    /// The per-version manifest URL already resolved by `check_valid_version`,
+    /// if any, so a `Vanilla` server doesn't refetch Mojang's top-level
+    /// manifest during `init_server`.
+    pub vanilla_manifest_entry_url: Option<String>,
 }
 
 impl Server {
-    pub async fn init_server(&mut self) {
-        send_info("Creating Working Directory.".to_string());
+    pub async fn init_server(&mut self) -> Result<(), String> {
+        info!("Creating Working Directory.");
         if self.wd == PathBuf::from("none") {
             let dir_name = format!("{:?}:{}-{}", self.software, self.version, generate_random_uuid());
-            self.wd = get_temp_folder().unwrap();
+            self.wd = get_temp_folder()?;
             self.wd.push("mcdevkit");
-            createdir(self.wd.clone());
+            createdir(self.wd.clone())?;
             self.wd.push(dir_name);
-            createdir(self.wd.clone());
-        } else if let Ok(full_path) = self.wd.canonicalize() {
-            self.wd = full_path
+            createdir(self.wd.clone())?;
         } else {
-            eprintln!("Error: Failed to get the full path.");
-            exit(1)
+            match self.wd.canonicalize() {
+                Ok(full_path) => self.wd = full_path,
+                Err(_) => return Err("Failed to get the full path.".to_string()),
+            }
         }
 
-        send_info("Downloading Server Software.".to_string());
-        download_server_software(self.software, self.version.clone(), self.wd.clone()).await;
+        info!("Downloading Server Software.");
+        download_server_software(self.software, self.version.clone(), self.wd.clone(), self.vanilla_manifest_entry_url.as_deref()).await?;
+
+        info!("Writing server.properties.");
+        write_server_properties(self.wd.clone(), &self.properties)?;
 
-        send_info("Creating Eula.txt.".to_string());
+        info!("Creating Eula.txt.");
         let mut path = self.wd.clone();
         path.push("eula.txt");
 
-        match File::create(&path) {
-            Ok(mut file) => {
-                if let Err(e) = file.write_all(b"eula=true") {
-                    eprintln!("Error writing to eula.txt: {}", e);
-                    exit(1);
-                }
-            }
-            Err(e) => {
-                eprintln!("Error creating eula.txt: {}", e);
-                exit(1);
-            }
+        let mut file = File::create(&path).map_err(|e| format!("Error creating eula.txt: {}", e))?;
+        file.write_all(b"eula=true").map_err(|e| format!("Error writing to eula.txt: {}", e))?;
+
+        let mut plugins_folder = self.wd.clone();
+        plugins_folder.push("plugins");
+        createdir(plugins_folder.clone())?;
+        copy_plugins(self.plugins.clone(), plugins_folder, &self.version, self.software).await;
+
+        if !self.worlds.is_empty() {
+            info!("Provisioning Worlds.");
+            let level_name = self.properties.get("level-name").cloned().unwrap_or_else(|| "world".to_string());
+            provision_worlds(&self.worlds, &self.wd, &level_name).await;
         }
 
+        Ok(())
+    }
+
+    pub async fn start_server(&self, watch: bool) -> Result<(), Box<dyn Error>> {
+        let mut signal = signal(SignalKind::interrupt())?;
+
+        let mut watcher = if watch {
+            Some(watch_plugins_dir(&self.wd)?)
+        } else {
+            None
+        };
+
         let mut plugins_folder = self.wd.clone();
         plugins_folder.push("plugins");
-        createdir(plugins_folder.clone());
-        copy_plugins(self.plugins.clone(), plugins_folder);
+
+        loop {
+            let mut command = build_java_command(&self.wd, "server.jar", Some(self.mem), &self.args);
+
+            command.stdout(Stdio::inherit())
+                .stdin(if watch { Stdio::piped() } else { Stdio::inherit() })
+                .stderr(Stdio::inherit());
+
+            let mut child = command.spawn()?;
+
+            tokio::select! {
+                _ = child.wait() => {
+                    break;
+                }
+                _ = signal.recv() => {
+                    let _ = child.kill().await;
+                    break;
+                }
+                _ = recv_watch_event(&mut watcher) => {
+                    info!("Plugin change detected, restarting server.");
+
+                    let remote_plugins = self.plugins.iter()
+                        .filter(|plugin| !matches!(plugin, PluginSource::Local(_)))
+                        .cloned()
+                        .collect();
+                    copy_plugins(remote_plugins, plugins_folder.clone(), &self.version, self.software).await;
+
+                    if let Some(stdin) = child.stdin.as_mut() {
+                        if let Err(e) = stdin.write_all(b"stop\n").await {
+                            error!("Failed to send stop command to server: {}", e);
+                        }
+                    }
+                    let _ = child.wait().await;
+
+                    // copy_plugins may itself have written into the watched
+                    // folder (remote downloads); give the watcher a moment
+                    // to deliver those self-generated events, then drop them
+                    // so they don't immediately trigger another restart.
+                    tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+                    drain_watch_events(&mut watcher);
+                }
+            }
+        }
+
+        Ok(())
     }
 
-    pub async fn start_server(&self) -> Result<(), Box<dyn Error>> {
-        let mut command = Command::new("java");
-        command.args(["-Xms256M", &format!("-Xmx{}M", self.mem), "-jar", "server.jar"]);
+}
+
+/// Watches `wd`/`plugins` for jar additions/changes, returning a channel that
+/// receives a message per change event. The returned watcher must be kept
+/// alive for as long as events are needed.
+fn watch_plugins_dir(wd: &Path) -> Result<(RecommendedWatcher, mpsc::UnboundedReceiver<()>), Box<dyn Error>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    let mut plugins_dir = wd.to_path_buf();
+    plugins_dir.push("plugins");
 
-        for arg in &self.args {
-            command.arg(arg);
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+        if let Ok(event) = res {
+            let touches_jar = event.paths.iter().any(|path| path.extension().map_or(false, |ext| ext == "jar"));
+            if touches_jar && matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                let _ = tx.send(());
+            }
         }
+    })?;
 
-        command.current_dir(&self.wd);
+    watcher.watch(&plugins_dir, RecursiveMode::NonRecursive)?;
 
-        command.stdout(Stdio::inherit())
-            .stdin(Stdio::inherit())
-            .stderr(Stdio::inherit());
+    Ok((watcher, rx))
+}
 
-        let mut child = command.spawn()?;
+async fn recv_watch_event(watcher: &mut Option<(RecommendedWatcher, mpsc::UnboundedReceiver<()>)>) -> Option<()> {
+    match watcher {
+        Some((_, rx)) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
 
-        let mut signal = signal(SignalKind::interrupt())?;
+/// Discards any events already buffered on the watch channel, so events the
+/// restart itself generated (e.g. `copy_plugins` writing into the watched
+/// folder) don't immediately trigger another restart.
+fn drain_watch_events(watcher: &mut Option<(RecommendedWatcher, mpsc::UnboundedReceiver<()>)>) {
+    if let Some((_, rx)) = watcher {
+        while rx.try_recv().is_ok() {}
+    }
+}
+
+/// Builds the `java -jar <jar_name>` invocation shared by standalone servers
+/// and network backends/proxies. `mem` is omitted for processes (e.g. a
+/// proxy) that don't take JVM heap flags.
+pub(crate) fn build_java_command(wd: &Path, jar_name: &str, mem: Option<u32>, args: &[String]) -> Command {
+    let mut command = Command::new("java");
+
+    if let Some(mem) = mem {
+        command.args(["-Xms256M", &format!("-Xmx{}M", mem)]);
+    }
+
+    command.args(["-jar", jar_name]);
+
+    for arg in args {
+        command.arg(arg);
+    }
+
+    command.current_dir(wd);
+    command
+}
+
+/// Waits for any of `children` to exit, or for Ctrl-C, then kills whatever
+/// is left of `children` either way. Used by `network::run_network` to tear
+/// its set of spawned Java processes down as soon as any one of them stops;
+/// `start_server` runs its own select loop instead.
+pub(crate) async fn wait_for_children_or_interrupt(children: &mut [Child]) -> Result<(), Box<dyn Error>> {
+    let mut signal = signal(SignalKind::interrupt())?;
+
+    {
+        let waits: Vec<_> = children.iter_mut().map(|child| Box::pin(child.wait())).collect();
 
         tokio::select! {
-            _ = child.wait() => {
-            }
-            _ = signal.recv() => {
-                let _ = child.kill().await;
-            }
+            _ = select_all(waits) => {}
+            _ = signal.recv() => {}
         }
+    }
 
-        Ok(())
+    for child in children.iter_mut() {
+        let _ = child.kill().await;
     }
 
+    Ok(())
 }
\ No newline at end of file
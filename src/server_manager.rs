@@ -1,14 +1,14 @@
 use std::{env, fs};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::Debug;
 use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
-use std::process::exit;
 
 use futures_util::StreamExt;
 use indicatif::{ProgressBar, ProgressStyle};
+use log::error;
 use rand::distributions::Alphanumeric;
 use rand::Rng;
 use regex::Regex;
@@ -16,8 +16,8 @@ use reqwest::Client;
 use serde::Deserialize;
 use tempdir::TempDir;
 
-use crate::send_info;
 use crate::server::Software;
+use crate::source::{FabricSource, ForgeSource, PaperSource, PurpurSource, QuiltSource, Source, SpigotSource, VanillaSource};
 
 #[derive(Debug, Deserialize)]
 
@@ -34,9 +34,10 @@ pub struct ApiVanillaLatestVersions {
 
 #[derive(Debug, Deserialize)]
 pub struct ApiVanillaVersionEntry {
-    id: String,
+    pub(crate) id: String,
     #[serde(rename = "type")]
     version_type: String,
+    pub(crate) url: String,
 }
 
 
@@ -55,7 +56,7 @@ pub fn generate_random_uuid() -> String {
     random_string
 }
 
-pub fn get_temp_folder() -> Result<PathBuf, std::io::Error> {
+pub fn get_temp_folder() -> Result<PathBuf, String> {
     #[cfg(unix)]
     {
         use std::fs;
@@ -76,43 +77,101 @@ pub fn get_temp_folder() -> Result<PathBuf, std::io::Error> {
 
     let temp_dir = match env::temp_dir().to_str() {
         Some(path) => path.to_string(),
-        None => return Err(std::io::Error::new(std::io::ErrorKind::Other, "Invalid temp directory path")),
+        None => return Err("Invalid temp directory path".to_string()),
     };
 
-    let temp_folder = TempDir::new_in(temp_dir, "mcdevkit-tmp")?;
-    Ok(temp_folder.into_path())
+    match TempDir::new_in(temp_dir, "mcdevkit-tmp") {
+        Ok(temp_folder) => Ok(temp_folder.into_path()),
+        Err(e) => Err(format!("Failed to create a temp folder: {}", e)),
+    }
 }
 
-pub fn createdir(dir: PathBuf) {
-    if !dir.exists() {
-        if let Err(err) = fs::create_dir(dir.clone()) {
-            eprintln!("Error creating directory: {}", err);
-            exit(1)
+const DEFAULT_PROPERTIES: &[(&str, &str)] = &[
+    ("online-mode", "false"),
+    ("server-port", "25565"),
+    ("motd", "A Minecraft Server"),
+    ("level-name", "world"),
+    ("gamemode", "survival"),
+    ("difficulty", "easy"),
+];
+
+fn escape_properties_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            ':' => escaped.push_str("\\:"),
+            '=' => escaped.push_str("\\="),
+            '#' => escaped.push_str("\\#"),
+            '!' => escaped.push_str("\\!"),
+            ' ' => escaped.push_str("\\ "),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) > 0x7E => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
         }
     }
+    escaped
 }
 
-pub async fn download_server_software(software: Software, version: String, wd: PathBuf) {
-    let mut downloadurl = String::new();
-
-    if software == Software::Paper {
-        match paper_get_download_link(Some(&version)).await {
-            Ok(download_link) => {
-                downloadurl = download_link;
-            },
-            Err(e) => {
-                eprintln!("Error: {}", e);
-                exit(1);
-            },
-        }
-        // } else if software == Software::Spigot {
+pub fn write_server_properties(wd: PathBuf, properties: &HashMap<String, String>) -> Result<(), String> {
+    let mut merged: HashMap<String, String> = DEFAULT_PROPERTIES.iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    merged.extend(properties.clone());
+
+    let mut keys: Vec<&String> = merged.keys().collect();
+    keys.sort();
+
+    let mut contents = String::new();
+    for key in keys {
+        contents.push_str(&escape_properties_value(key));
+        contents.push('=');
+        contents.push_str(&escape_properties_value(&merged[key]));
+        contents.push('\n');
     }
 
-    if let Err(err) = download_file(&downloadurl, &wd, "server.jar").await {
-        eprintln!("Error: {}", err);
+    let mut path = wd;
+    path.push("server.properties");
+
+    let mut file = match File::create(&path) {
+        Ok(file) => file,
+        Err(e) => return Err(format!("Error creating server.properties: {}", e)),
+    };
+
+    match file.write_all(contents.as_bytes()) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(format!("Error writing to server.properties: {}", e)),
     }
 }
 
+pub fn createdir(dir: PathBuf) -> Result<(), String> {
+    if !dir.exists() {
+        if let Err(err) = fs::create_dir(dir.clone()) {
+            return Err(format!("Error creating directory: {}", err));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn download_server_software(software: Software, version: String, wd: PathBuf, vanilla_manifest_entry_url: Option<&str>) -> Result<(), String> {
+    let resolved = match software {
+        Software::Paper => PaperSource.resolve_download_url(&version, None).await,
+        Software::Vanilla => VanillaSource.resolve_download_url(&version, vanilla_manifest_entry_url).await,
+        Software::Purpur => PurpurSource.resolve_download_url(&version, None).await,
+        Software::Fabric => FabricSource.resolve_download_url(&version, None).await,
+        Software::Forge => ForgeSource.resolve_download_url(&version, None).await,
+        Software::Quilt => QuiltSource.resolve_download_url(&version, None).await,
+        Software::Spigot => SpigotSource.resolve_download_url(&version, None).await,
+    };
+
+    let downloadurl = resolved?;
+
+    download_file(&downloadurl, &wd, "server.jar").await.map_err(|e| format!("Error downloading server software: {}", e))
+}
+
 pub async fn paper_get_download_link(version: Option<&str>) -> Result<String, String> {
     let url = "https://qing762.is-a.dev/api/papermc";
     let response = match reqwest::get(url).await {
@@ -140,7 +199,7 @@ pub async fn paper_get_download_link(version: Option<&str>) -> Result<String, St
     }
 }
 
-pub fn copy_file_to_folder(file_path: PathBuf, folder_path: PathBuf) -> std::io::Result<()> {
+pub(crate) fn copy_file_to_folder(file_path: PathBuf, folder_path: PathBuf) -> std::io::Result<()> {
     if !folder_path.is_dir() {
         return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Destination folder does not exist"));
     }
@@ -158,35 +217,7 @@ pub fn copy_file_to_folder(file_path: PathBuf, folder_path: PathBuf) -> std::io:
     Ok(())
 }
 
-pub fn copy_plugins(plugins: Vec<PathBuf>, plugins_folder: PathBuf) {
-    if !plugins_folder.exists() {
-        eprintln!("Destination folder does not exist: {:?}", plugins_folder);
-        return;
-    }
-
-    if !plugins_folder.is_dir() {
-        eprintln!("Destination path is not a directory: {:?}", plugins_folder);
-        return;
-    }
-
-    for plugin in plugins {
-        if !plugin.exists() {
-            eprintln!("{:?} does not exist. Skipping...", plugin);
-            continue;
-        }
-
-        if plugin.is_file() {
-            match copy_file_to_folder(plugin.clone(), plugins_folder.clone()) {
-                Ok(()) => send_info(format!("{} moved to plugins Folder.", plugin.display())),
-                Err(e) => eprintln!("Failed to copy {}: {}", plugin.display(), e),
-            }
-        } else {
-            eprintln!("{:?} is not a file. Skipping...", plugin);
-        }
-    }
-}
-
-async fn download_file(url: &str, save_dir: &PathBuf, file_name: &str) -> Result<(), Box<dyn Error>> {
+pub(crate) async fn download_file(url: &str, save_dir: &PathBuf, file_name: &str) -> Result<(), Box<dyn Error>> {
     let client = Client::new();
     let response = client.get(url).send().await?;
     let content_length = response.content_length().unwrap_or(0);
@@ -216,43 +247,87 @@ async fn download_file(url: &str, save_dir: &PathBuf, file_name: &str) -> Result
     Ok(())
 }
 
-pub async fn check_valid_version(version_to_check: &str) -> bool {
+/// Validates `version_to_check` against Mojang's version manifest, returning
+/// the matched entry's per-version manifest URL on success so callers (e.g.
+/// `VanillaSource`) can reuse this fetch instead of repeating it.
+pub async fn check_valid_version(version_to_check: &str) -> Option<String> {
     let version_regex_pattern = r"^1.\d{1,2}.?\d{1,2}$";
     let version_regex = Regex::new(version_regex_pattern).unwrap();
 
     if !version_regex.is_match(version_to_check) {
-        eprintln!("Error: '{}' is not a valid version number.", version_to_check);
-        return false;
+        error!("'{}' is not a valid version number.", version_to_check);
+        return None;
     }
 
     let url = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
     let response = match reqwest::get(url).await {
         Ok(resp) => resp,
         Err(e) => {
-            eprintln!("Error: Failed to fetch version manifest - {}", e);
-            return false;
+            error!("Failed to fetch version manifest - {}", e);
+            return None;
         }
     };
 
     if !response.status().is_success() {
-        eprintln!("Error: Failed to fetch version manifest - Status code {}", response.status());
-        return false;
+        error!("Failed to fetch version manifest - Status code {}", response.status());
+        return None;
     }
 
     let json_response: VanillaApiResponse = match response.json().await {
         Ok(resp) => resp,
         Err(e) => {
-            eprintln!("Error: Failed to parse JSON response - {}", e);
-            return false;
+            error!("Failed to parse JSON response - {}", e);
+            return None;
         }
     };
 
-    let available_versions: HashSet<String> = json_response.versions.into_iter().map(|entry| entry.id).collect();
+    match json_response.versions.into_iter().find(|entry| entry.id == version_to_check) {
+        Some(entry) => Some(entry.url),
+        None => {
+            error!("Version {} not found in version manifest.", version_to_check);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_properties_special_characters() {
+        assert_eq!(escape_properties_value("a:b=c#d!e f"), "a\\:b\\=c\\#d\\!e\\ f");
+    }
+
+    #[test]
+    fn escapes_properties_control_characters() {
+        assert_eq!(escape_properties_value("a\nb\rc\td\\e"), "a\\nb\\rc\\td\\\\e");
+    }
 
-    if !available_versions.contains(version_to_check) {
-        eprintln!("Error: Version {} not found in version manifest.", version_to_check);
-        return false;
+    #[test]
+    fn escapes_properties_non_ascii_as_unicode_escapes() {
+        assert_eq!(escape_properties_value("caf\u{e9}"), "caf\\u00e9");
     }
 
-    true
+    #[test]
+    fn write_server_properties_merges_defaults_and_overrides_in_sorted_order() {
+        let dir = TempDir::new("mcdevkit-test").unwrap();
+        let wd = dir.path().to_path_buf();
+
+        let mut properties = HashMap::new();
+        properties.insert("level-name".to_string(), "caf\u{e9}".to_string());
+        properties.insert("motd".to_string(), "Hi!".to_string());
+
+        write_server_properties(wd.clone(), &properties).unwrap();
+
+        let contents = fs::read_to_string(wd.join("server.properties")).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        let mut sorted_lines = lines.clone();
+        sorted_lines.sort();
+        assert_eq!(lines, sorted_lines);
+
+        assert!(lines.contains(&"level-name=caf\\u00e9"));
+        assert!(lines.contains(&"motd=Hi\\!"));
+        assert!(lines.contains(&"online-mode=false"));
+    }
 }
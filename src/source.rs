@@ -0,0 +1,208 @@
+use serde::Deserialize;
+
+use crate::server_manager::{paper_get_download_link, VanillaApiResponse};
+
+/// Resolves the download URL for a piece of server software at a given version.
+///
+/// Each `Software` variant gets its own implementor so `download_server_software`
+/// doesn't need to know how any particular upstream works. `manifest_entry_url`
+/// is the per-version manifest URL already resolved by `check_valid_version`
+/// (Mojang's `version_manifest_v2.json` lookup), when available, so `VanillaSource`
+/// doesn't have to fetch that manifest a second time; other sources ignore it.
+pub trait Source {
+    async fn resolve_download_url(&self, version: &str, manifest_entry_url: Option<&str>) -> Result<String, String>;
+}
+
+pub struct PaperSource;
+pub struct VanillaSource;
+pub struct PurpurSource;
+pub struct FabricSource;
+pub struct QuiltSource;
+pub struct ForgeSource;
+pub struct SpigotSource;
+
+impl Source for PaperSource {
+    async fn resolve_download_url(&self, version: &str, _manifest_entry_url: Option<&str>) -> Result<String, String> {
+        paper_get_download_link(Some(version)).await
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VanillaVersionManifest {
+    downloads: VanillaVersionDownloads,
+}
+
+#[derive(Debug, Deserialize)]
+struct VanillaVersionDownloads {
+    server: VanillaVersionDownloadEntry,
+}
+
+#[derive(Debug, Deserialize)]
+struct VanillaVersionDownloadEntry {
+    url: String,
+}
+
+impl Source for VanillaSource {
+    async fn resolve_download_url(&self, version: &str, manifest_entry_url: Option<&str>) -> Result<String, String> {
+        let entry_url = match manifest_entry_url {
+            Some(entry_url) => entry_url.to_string(),
+            None => fetch_vanilla_manifest_entry_url(version).await?,
+        };
+
+        let version_response = match reqwest::get(&entry_url).await {
+            Ok(resp) => resp,
+            Err(e) => return Err(format!("Failed to fetch version metadata: {}", e)),
+        };
+
+        if !version_response.status().is_success() {
+            return Err(format!("Failed to fetch version metadata: Status code {}", version_response.status()));
+        }
+
+        let version_manifest: VanillaVersionManifest = match version_response.json().await {
+            Ok(resp) => resp,
+            Err(e) => return Err(format!("Failed to parse version metadata: {}", e)),
+        };
+
+        Ok(version_manifest.downloads.server.url)
+    }
+}
+
+/// Looks up `version`'s per-version manifest URL from Mojang's top-level
+/// `version_manifest_v2.json`. Only hit when the caller didn't already have
+/// this from `check_valid_version`.
+async fn fetch_vanilla_manifest_entry_url(version: &str) -> Result<String, String> {
+    let url = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+    let response = match reqwest::get(url).await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to fetch version manifest: {}", e)),
+    };
+
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch version manifest: Status code {}", response.status()));
+    }
+
+    let manifest: VanillaApiResponse = match response.json().await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to parse version manifest: {}", e)),
+    };
+
+    match manifest.versions.into_iter().find(|entry| entry.id == version) {
+        Some(entry) => Ok(entry.url),
+        None => Err(format!("Version {} not found in version manifest.", version)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PurpurApiResponse {
+    builds: PurpurApiBuilds,
+}
+
+#[derive(Debug, Deserialize)]
+struct PurpurApiBuilds {
+    latest: String,
+}
+
+impl Source for PurpurSource {
+    async fn resolve_download_url(&self, version: &str, _manifest_entry_url: Option<&str>) -> Result<String, String> {
+        let url = format!("https://api.purpurmc.org/v2/purpur/{}", version);
+        let response = match reqwest::get(&url).await {
+            Ok(resp) => resp,
+            Err(e) => return Err(format!("Failed to fetch Purpur API response: {}", e)),
+        };
+
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch Purpur API response: Status code {}", response.status()));
+        }
+
+        let json_response: PurpurApiResponse = match response.json().await {
+            Ok(resp) => resp,
+            Err(e) => return Err(format!("Failed to parse Purpur API response: {}", e)),
+        };
+
+        Ok(format!("https://api.purpurmc.org/v2/purpur/{}/{}/download", version, json_response.builds.latest))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LoaderVersionEntry {
+    loader: LoaderVersionInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct LoaderVersionInfo {
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstallerVersionEntry {
+    version: String,
+    stable: bool,
+}
+
+async fn fetch_meta_loader_jar_url(meta_host: &str, api_version: &str, version: &str) -> Result<String, String> {
+    let loader_url = format!("{}/{}/versions/loader/{}", meta_host, api_version, version);
+    let loader_response = match reqwest::get(&loader_url).await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to fetch loader versions: {}", e)),
+    };
+
+    if !loader_response.status().is_success() {
+        return Err(format!("Failed to fetch loader versions: Status code {}", loader_response.status()));
+    }
+
+    let loader_versions: Vec<LoaderVersionEntry> = match loader_response.json().await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to parse loader versions: {}", e)),
+    };
+
+    let loader_version = match loader_versions.first() {
+        Some(entry) => entry.loader.version.clone(),
+        None => return Err(format!("No loader versions available for {}.", version)),
+    };
+
+    let installer_url = format!("{}/{}/versions/installer", meta_host, api_version);
+    let installer_response = match reqwest::get(&installer_url).await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to fetch installer versions: {}", e)),
+    };
+
+    if !installer_response.status().is_success() {
+        return Err(format!("Failed to fetch installer versions: Status code {}", installer_response.status()));
+    }
+
+    let installer_versions: Vec<InstallerVersionEntry> = match installer_response.json().await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Failed to parse installer versions: {}", e)),
+    };
+
+    let installer_version = match installer_versions.iter().find(|entry| entry.stable).or_else(|| installer_versions.first()) {
+        Some(entry) => entry.version.clone(),
+        None => return Err("No installer versions available.".to_string()),
+    };
+
+    Ok(format!("{}/{}/versions/loader/{}/{}/{}/server/jar", meta_host, api_version, version, loader_version, installer_version))
+}
+
+impl Source for FabricSource {
+    async fn resolve_download_url(&self, version: &str, _manifest_entry_url: Option<&str>) -> Result<String, String> {
+        fetch_meta_loader_jar_url("https://meta.fabricmc.net", "v2", version).await
+    }
+}
+
+impl Source for QuiltSource {
+    async fn resolve_download_url(&self, version: &str, _manifest_entry_url: Option<&str>) -> Result<String, String> {
+        fetch_meta_loader_jar_url("https://meta.quiltmc.org", "v3", version).await
+    }
+}
+
+impl Source for ForgeSource {
+    async fn resolve_download_url(&self, _version: &str, _manifest_entry_url: Option<&str>) -> Result<String, String> {
+        Err("Forge is not yet supported: its installer has to be run rather than downloaded as a plain jar.".to_string())
+    }
+}
+
+impl Source for SpigotSource {
+    async fn resolve_download_url(&self, _version: &str, _manifest_entry_url: Option<&str>) -> Result<String, String> {
+        Err("Spigot is not yet supported: it has to be built locally with BuildTools rather than downloaded.".to_string())
+    }
+}
@@ -0,0 +1,221 @@
+use std::fs;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use log::{error, info};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::server_manager::download_file;
+
+/// Downloads/locates each world archive and unzips it into `wd`, remapping
+/// the archive's `world`/`world_nether`/`world_the_end` folders onto the
+/// server's configured `level_name`.
+pub async fn provision_worlds(specs: &[String], wd: &Path, level_name: &str) {
+    for spec in specs {
+        match provision_world(spec, wd, level_name).await {
+            Ok(()) => info!("World provisioned from {}.", spec),
+            Err(e) => error!("Failed to provision world from {}: {}", spec, e),
+        }
+    }
+}
+
+async fn provision_world(spec: &str, wd: &Path, level_name: &str) -> Result<(), String> {
+    let is_remote = spec.starts_with("http://") || spec.starts_with("https://");
+
+    let archive_path = if is_remote {
+        let file_name = spec.rsplit('/').next().filter(|name| !name.is_empty()).unwrap_or("world.zip").to_string();
+        if let Err(e) = download_file(spec, &wd.to_path_buf(), &file_name).await {
+            return Err(format!("Failed to download {}: {}", spec, e));
+        }
+
+        let mut downloaded = wd.to_path_buf();
+        downloaded.push(file_name);
+        downloaded
+    } else {
+        PathBuf::from(spec)
+    };
+
+    let result = extract_world_archive(&archive_path, wd, level_name);
+
+    if is_remote {
+        let _ = fs::remove_file(&archive_path);
+    }
+
+    result
+}
+
+fn remap_world_dim(entry_path: &Path, level_name: &str) -> PathBuf {
+    let mut components = entry_path.components();
+    let first = match components.next() {
+        Some(first) => first,
+        None => return entry_path.to_path_buf(),
+    };
+
+    let remapped_root = match first.as_os_str().to_string_lossy().as_ref() {
+        "world" => level_name.to_string(),
+        "world_nether" => format!("{}_nether", level_name),
+        "world_the_end" => format!("{}_the_end", level_name),
+        other => other.to_string(),
+    };
+
+    let mut result = PathBuf::from(remapped_root);
+    result.extend(components);
+    result
+}
+
+fn extract_world_archive(archive_path: &Path, wd: &Path, level_name: &str) -> Result<(), String> {
+    let file = match File::open(archive_path) {
+        Ok(f) => f,
+        Err(e) => return Err(format!("Failed to open {}: {}", archive_path.display(), e)),
+    };
+
+    let mut archive = match ZipArchive::new(file) {
+        Ok(archive) => archive,
+        Err(e) => return Err(format!("Failed to read {} as a zip archive: {}", archive_path.display(), e)),
+    };
+
+    for i in 0..archive.len() {
+        let mut entry = match archive.by_index(i) {
+            Ok(entry) => entry,
+            Err(e) => return Err(format!("Failed to read archive entry: {}", e)),
+        };
+
+        let entry_path = match entry.enclosed_name() {
+            Some(path) => path.to_path_buf(),
+            None => continue,
+        };
+
+        let mut out_path = wd.to_path_buf();
+        out_path.push(remap_world_dim(&entry_path, level_name));
+
+        if entry.is_dir() {
+            if let Err(e) = fs::create_dir_all(&out_path) {
+                return Err(format!("Failed to create {}: {}", out_path.display(), e));
+            }
+            continue;
+        }
+
+        if let Some(parent) = out_path.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                return Err(format!("Failed to create {}: {}", parent.display(), e));
+            }
+        }
+
+        let mut out_file = match File::create(&out_path) {
+            Ok(f) => f,
+            Err(e) => return Err(format!("Failed to create {}: {}", out_path.display(), e)),
+        };
+
+        if let Err(e) = std::io::copy(&mut entry, &mut out_file) {
+            return Err(format!("Failed to extract {}: {}", out_path.display(), e));
+        }
+    }
+
+    Ok(())
+}
+
+fn add_dir_to_zip(zip: &mut ZipWriter<File>, dir: &Path, zip_prefix: &str, options: FileOptions) -> Result<(), String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => return Err(format!("Failed to read entry in {}: {}", dir.display(), e)),
+        };
+
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let zip_path = format!("{}/{}", zip_prefix, name);
+
+        if path.is_dir() {
+            add_dir_to_zip(zip, &path, &zip_path, options)?;
+            continue;
+        }
+
+        if let Err(e) = zip.start_file(zip_path.clone(), options) {
+            return Err(format!("Failed to add {} to archive: {}", zip_path, e));
+        }
+
+        let mut file = match File::open(&path) {
+            Ok(f) => f,
+            Err(e) => return Err(format!("Failed to open {}: {}", path.display(), e)),
+        };
+
+        if let Err(e) = std::io::copy(&mut file, zip) {
+            return Err(format!("Failed to write {} to archive: {}", zip_path, e));
+        }
+    }
+
+    Ok(())
+}
+
+/// Zips a live `level_name` world (and its `_nether`/`_the_end` dims, if present)
+/// out of `wd` into `output`, re-rooting it onto the canonical `world`/
+/// `world_nether`/`world_the_end` names so `provision_world` can remap it
+/// onto whatever `level_name` the importing server is configured with.
+pub fn export_world(wd: &Path, level_name: &str, output: &Path) -> Result<(), String> {
+    let file = match File::create(output) {
+        Ok(f) => f,
+        Err(e) => return Err(format!("Failed to create {}: {}", output.display(), e)),
+    };
+
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut exported_any = false;
+    for (dim_suffix, archive_root) in [("", "world"), ("_nether", "world_nether"), ("_the_end", "world_the_end")] {
+        let dim_name = format!("{}{}", level_name, dim_suffix);
+        let mut dim_path = wd.to_path_buf();
+        dim_path.push(&dim_name);
+
+        if !dim_path.is_dir() {
+            continue;
+        }
+
+        add_dir_to_zip(&mut zip, &dim_path, archive_root, options)?;
+        exported_any = true;
+    }
+
+    if !exported_any {
+        return Err(format!("No world named {} found in {}.", level_name, wd.display()));
+    }
+
+    match zip.finish() {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("Failed to finalize {}: {}", output.display(), e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaps_overworld_root() {
+        assert_eq!(remap_world_dim(Path::new("world/level.dat"), "myworld"), PathBuf::from("myworld/level.dat"));
+    }
+
+    #[test]
+    fn remaps_nether_root() {
+        assert_eq!(remap_world_dim(Path::new("world_nether/level.dat"), "myworld"), PathBuf::from("myworld_nether/level.dat"));
+    }
+
+    #[test]
+    fn remaps_the_end_root() {
+        assert_eq!(remap_world_dim(Path::new("world_the_end/level.dat"), "myworld"), PathBuf::from("myworld_the_end/level.dat"));
+    }
+
+    #[test]
+    fn leaves_unrelated_roots_unchanged() {
+        assert_eq!(remap_world_dim(Path::new("other/level.dat"), "myworld"), PathBuf::from("other/level.dat"));
+    }
+
+    #[test]
+    fn leaves_default_level_name_unchanged() {
+        assert_eq!(remap_world_dim(Path::new("world/level.dat"), "world"), PathBuf::from("world/level.dat"));
+    }
+}